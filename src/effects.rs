@@ -0,0 +1,148 @@
+//! A small frame-based animation engine built on top of [LedDmaBuffer].
+//! Implement [Effect] to describe an animation, and drive it one frame at a time with
+//! [EffectDriver] instead of hand-writing the loop (index rotation, brightness breathing, ...)
+//! as the examples in this crate currently do.
+
+use crate::{LedDmaBuffer, LedDmaError, RgbLedColor, HSV, RGB};
+
+/// A frame-based LED animation, advanced one tick at a time by [EffectDriver].
+pub trait Effect<T> {
+    /// Renders one frame into `out`, given the current frame counter `tick`.
+    fn render(&mut self, out: &mut [T], tick: u32);
+}
+
+/// A simple xorshift32 PRNG, used by effects (e.g. [FireFlicker]) that need per-pixel
+/// randomization without pulling in a `rand` dependency.
+struct XorShift32(u32);
+impl XorShift32 {
+    const fn new(seed: u32) -> Self {
+        Self(if seed == 0 { 1 } else { seed })
+    }
+    fn next(&mut self) -> u32 {
+        let mut x = self.0;
+        x ^= x << 13;
+        x ^= x >> 17;
+        x ^= x << 5;
+        self.0 = x;
+        x
+    }
+}
+
+/// Cycles a rainbow gradient across the strip, built on [HSV] hue rotation.
+pub struct RainbowCycle {
+    speed: u8,
+}
+impl RainbowCycle {
+    /// * `speed` - Hue steps advanced per tick.
+    pub const fn new(speed: u8) -> Self {
+        Self { speed }
+    }
+}
+impl Effect<RGB> for RainbowCycle {
+    fn render(&mut self, out: &mut [RGB], tick: u32) {
+        let len = out.len().max(1) as u32;
+        let hue_offset = (tick.wrapping_mul(self.speed as u32) & 0xFF) as u8;
+        for (i, pixel) in out.iter_mut().enumerate() {
+            let hue = ((i as u32 * 255 / len) as u8).wrapping_add(hue_offset);
+            *pixel = HSV::new(hue, 255, 255).to_rgb();
+        }
+    }
+}
+
+/// A flame-flicker effect, based on WLED's `mode_fire_flicker`: each pixel is `base` with a
+/// random per-channel dip bounded by `base`'s luminance, redrawn every `interval` ticks.
+pub struct FireFlicker {
+    base: RGB,
+    intensity: u8,
+    interval: u32,
+    rng: XorShift32,
+}
+impl FireFlicker {
+    /// * `base` - The ember color to flicker around.
+    /// * `intensity` - `0-255`. Higher intensity widens the flicker, giving a less steady flame.
+    /// * `interval` - Redraw the flicker every `interval` ticks, so the flicker speed can be
+    ///   tuned independently of the caller's frame rate.
+    /// * `seed` - PRNG seed; any nonzero value gives a different flicker pattern.
+    pub const fn new(base: RGB, intensity: u8, interval: u32, seed: u32) -> Self {
+        Self {
+            base,
+            intensity,
+            interval: if interval == 0 { 1 } else { interval },
+            rng: XorShift32::new(seed),
+        }
+    }
+}
+impl Effect<RGB> for FireFlicker {
+    fn render(&mut self, out: &mut [RGB], tick: u32) {
+        if !tick.is_multiple_of(self.interval) {
+            return;
+        }
+        let divisor = (256 - self.intensity as u16) / 16 + 1;
+        let max_channel = self.base.r.max(self.base.g).max(self.base.b);
+        let lum = ((max_channel as u16 / divisor).max(1)) as u32;
+        for pixel in out.iter_mut() {
+            let flicker_r = (self.rng.next() % lum) as u8;
+            let flicker_g = (self.rng.next() % lum) as u8;
+            let flicker_b = (self.rng.next() % lum) as u8;
+            *pixel = RGB::new(
+                self.base.r.saturating_sub(flicker_r),
+                self.base.g.saturating_sub(flicker_g),
+                self.base.b.saturating_sub(flicker_b),
+            );
+        }
+    }
+}
+
+/// Progressively fills the strip with `color`, one additional pixel every `speed` ticks,
+/// like a classic "color wipe" effect.
+pub struct ColorWipe {
+    color: RGB,
+    speed: u32,
+}
+impl ColorWipe {
+    /// * `color` - The color to wipe in.
+    /// * `speed` - Ticks to wait before lighting each additional pixel.
+    pub const fn new(color: RGB, speed: u32) -> Self {
+        Self {
+            color,
+            speed: if speed == 0 { 1 } else { speed },
+        }
+    }
+}
+impl Effect<RGB> for ColorWipe {
+    fn render(&mut self, out: &mut [RGB], tick: u32) {
+        let lit = ((tick / self.speed) as usize).min(out.len());
+        for (i, pixel) in out.iter_mut().enumerate() {
+            *pixel = if i < lit { self.color } else { RGB::new(0, 0, 0) };
+        }
+    }
+}
+
+/// Owns a [LedDmaBuffer] and a scratch pixel array, driving an [Effect] one frame at a time:
+/// [EffectDriver::advance] renders the effect into the scratch array, then writes it to the
+/// DMA buffer via [LedDmaBuffer::set_dma_buffer].
+pub struct EffectDriver<'a, T: RgbLedColor, const DMA_BUFFER_LEN: usize, const N: usize> {
+    led_dma_buffer: &'a mut LedDmaBuffer<DMA_BUFFER_LEN>,
+    pixels: [T; N],
+    tick: u32,
+}
+impl<'a, T: RgbLedColor, const DMA_BUFFER_LEN: usize, const N: usize>
+    EffectDriver<'a, T, DMA_BUFFER_LEN, N>
+{
+    /// * `led_dma_buffer` - The DMA buffer to render into.
+    /// * `initial` - Color the scratch array starts filled with, before the first frame.
+    pub fn new(led_dma_buffer: &'a mut LedDmaBuffer<DMA_BUFFER_LEN>, initial: T) -> Self {
+        Self {
+            led_dma_buffer,
+            pixels: [initial; N],
+            tick: 0,
+        }
+    }
+    /// Renders one frame of `effect` into the scratch array and writes it to the DMA buffer.
+    pub fn advance<E: Effect<T>>(&mut self, effect: &mut E) -> Result<(), LedDmaError> {
+        effect.render(&mut self.pixels, self.tick);
+        self.led_dma_buffer.set_dma_buffer(&self.pixels, None)?;
+        self.tick = self.tick.wrapping_add(1);
+        Ok(())
+    }
+}
@@ -1,9 +1,9 @@
-#![no_std]
+#![cfg_attr(not(test), no_std)]
 //! # Led Dma Buffers
 //! Used for creating a PWM waveform using the DMA to control RGB LEDs such as WS2812, WS2812B, SK6812, SK6812-RGBW.
 //! Based on Phil's Lab's RGB Led [video](https://www.youtube.com/watch?v=MqbJTj0Cw6o)
 //! # Example
-//! ```
+//! ```ignore
 //! const led_array: [RGB; 3] = [RGB::new(255,0,0), RGB::new(0,0,255), RGB::new(0,0,255)];
 //! // Calculate the dma buffer's length at compile time
 //! const DMA_BUFFER_LEN: usize = calc_dma_buffer_length(RGB::BIT_COUNT, led_array.len(), RESET_LENGTH);
@@ -22,6 +22,8 @@ use core::fmt::Debug;
 #[cfg(feature = "defmt")]
 use defmt::error;
 
+pub mod effects;
+
 /// Implemented by [RGB] & [RGBW]
 pub trait RgbLedColor: Copy + Clone {
     /// The number of bits representing the color
@@ -33,14 +35,77 @@ pub trait RgbLedColor: Copy + Clone {
     );
 }
 
-/// The order of colors of the data sent to the LED
+/// The order of colors of the data sent to the LED, covering every permutation of the R, G,
+/// and B channels. Real strips and off-spec clones ship in all six orderings, not just the
+/// two (RGB/GRB) seen most often in datasheets.
 /// ## Example
 /// If the data structure is R\[7:0] | G\[7:0] | B\[7:0] | W\[7:0] as seen
 /// [here](https://cdn-shop.adafruit.com/product-files/2757/p2757_SK6812RGBW_REV01.pdf#page=6) in the sk6812rgbw datasheet
 /// then use [LedDataComposition::RGB]
+#[derive(Clone, Copy)]
 pub enum LedDataComposition {
     RGB,
+    RBG,
     GRB,
+    GBR,
+    BRG,
+    BGR,
+}
+impl LedDataComposition {
+    /// Returns `(r_rank, g_rank, b_rank)`, each in `0..3`, giving the relative order the
+    /// three channels are sent in (rank `0` is sent first).
+    const fn rank(&self) -> (usize, usize, usize) {
+        match self {
+            LedDataComposition::RGB => (0, 1, 2),
+            LedDataComposition::RBG => (0, 2, 1),
+            LedDataComposition::GRB => (1, 0, 2),
+            LedDataComposition::GBR => (2, 0, 1),
+            LedDataComposition::BRG => (1, 2, 0),
+            LedDataComposition::BGR => (2, 1, 0),
+        }
+    }
+}
+
+/// The byte slot (`0..4`) that the white channel occupies within an RGBW LED's 32-bit data
+/// frame, independent of the relative order of R, G, and B set by [LedDataComposition].
+#[derive(Clone, Copy)]
+pub enum WhitePosition {
+    First,
+    Second,
+    Third,
+    Fourth,
+}
+impl WhitePosition {
+    const fn slot(&self) -> usize {
+        match self {
+            WhitePosition::First => 0,
+            WhitePosition::Second => 1,
+            WhitePosition::Third => 2,
+            WhitePosition::Fourth => 3,
+        }
+    }
+}
+
+/// Maps `(r_rank, g_rank, b_rank)` plus a white byte slot to the four actual byte slots
+/// `(r_slot, g_slot, b_slot, w_slot)` within an RGBW frame: R, G, and B fill whichever three
+/// slots are left over, in rank order, once the white slot is taken out.
+const fn rgbw_slots(rank: (usize, usize, usize), white_slot: usize) -> (usize, usize, usize, usize) {
+    let mut available = [0usize; 3];
+    let mut idx = 0;
+    let mut slot = 0;
+    while slot < 4 {
+        if slot != white_slot {
+            available[idx] = slot;
+            idx += 1;
+        }
+        slot += 1;
+    }
+    (
+        available[rank.0],
+        available[rank.1],
+        available[rank.2],
+        white_slot,
+    )
 }
 
 /// Error Types
@@ -62,6 +127,35 @@ impl RGB {
     pub const fn new(r: u8, g: u8, b: u8) -> Self {
         Self { r, g, b }
     }
+    /// Converts this color to [RGBW] by extracting its common white component, so RGB
+    /// content (e.g. a rainbow or media feed) can drive an RGBW strip's dedicated white LED
+    /// instead of leaving it dark.
+    pub const fn to_rgbw(&self, mode: WhiteExtractionMode) -> RGBW {
+        match mode {
+            WhiteExtractionMode::Maximum => {
+                let w = min3(self.r, self.g, self.b);
+                RGBW::new(self.r - w, self.g - w, self.b - w, w)
+            }
+        }
+    }
+}
+
+/// The algorithm used by [RGB::to_rgbw] to extract a white component from an RGB color.
+#[derive(Clone, Copy)]
+pub enum WhiteExtractionMode {
+    /// Extracts the maximum possible white, `w = min(r, g, b)`, giving the brightest and
+    /// most power-efficient result. Room is left here for an "accurate"/legacy mode that
+    /// trades some of that brightness for more faithful RGB hues.
+    Maximum,
+}
+
+const fn min3(a: u8, b: u8, c: u8) -> u8 {
+    let ab = if a < b { a } else { b };
+    if ab < c {
+        ab
+    } else {
+        c
+    }
 }
 impl RgbLedColor for RGB {
     const BIT_COUNT: usize = 8 * 3;
@@ -71,19 +165,58 @@ impl RgbLedColor for RGB {
         led_dma_buffer: &mut LedDmaBuffer<DMA_BUFFER_LEN>,
         led_index: usize,
     ) {
-        match led_dma_buffer.data_composition {
-            LedDataComposition::GRB => {
-                led_dma_buffer.set_byte(self.g, led_index);
-                led_dma_buffer.set_byte(self.r, led_index + 8);
-            }
-            LedDataComposition::RGB => {
-                led_dma_buffer.set_byte(self.r, led_index);
-                led_dma_buffer.set_byte(self.g, led_index + 8);
-            }
+        let (r_rank, g_rank, b_rank) = led_dma_buffer.data_composition.rank();
+        led_dma_buffer.set_byte(self.r, led_index + r_rank * 8);
+        led_dma_buffer.set_byte(self.g, led_index + g_rank * 8);
+        led_dma_buffer.set_byte(self.b, led_index + b_rank * 8);
+    }
+}
+/// Represents a color in the HSV (hue, saturation, value) color space.
+/// Useful for driving hue-rotation effects such as rainbow or hue-cycling animations,
+/// where stepping `h` each frame is more natural than hand-picking RGB values.
+#[derive(Clone, Copy)]
+pub struct HSV {
+    h: u8,
+    s: u8,
+    v: u8,
+}
+impl HSV {
+    pub const fn new(h: u8, s: u8, v: u8) -> Self {
+        Self { h, s, v }
+    }
+    /// Converts this HSV color to [RGB] using the standard 6-sector conversion,
+    /// computed with integer math only so it can run in `no_std`.
+    pub const fn to_rgb(&self) -> RGB {
+        if self.s == 0 {
+            return RGB::new(self.v, self.v, self.v);
+        }
+        let region = self.h / 43;
+        let remainder = (self.h % 43) * 6;
+
+        let p = (self.v as u16 * (255 - self.s) as u16 / 255) as u8;
+        let q = (self.v as u16 * (255 - (self.s as u16 * remainder as u16) / 255) / 255) as u8;
+        let t = (self.v as u16 * (255 - (self.s as u16 * (255 - remainder) as u16) / 255) / 255)
+            as u8;
+
+        match region {
+            0 => RGB::new(self.v, t, p),
+            1 => RGB::new(q, self.v, p),
+            2 => RGB::new(p, self.v, t),
+            3 => RGB::new(p, q, self.v),
+            4 => RGB::new(t, p, self.v),
+            _ => RGB::new(self.v, p, q),
         }
-        led_dma_buffer.set_byte(self.b, led_index + 16);
     }
 }
+
+/// Rotates the hue of every pixel in `pixels` by `step`, wrapping around at 255.
+/// Intended to be called once per frame before handing the array to [LedDmaBuffer::set_dma_buffer].
+pub fn rotate_hue(pixels: &mut [HSV], step: u8) {
+    for pixel in pixels.iter_mut() {
+        pixel.h = pixel.h.wrapping_add(step);
+    }
+}
+
 /// Represents a RGBW LED
 #[derive(Clone, Copy)]
 pub struct RGBW {
@@ -96,6 +229,90 @@ impl RGBW {
     pub const fn new(r: u8, g: u8, b: u8, w: u8) -> Self {
         Self { r, g, b, w }
     }
+    /// Derives an [RGBW] color from `rgb`, deriving the white channel's brightness from
+    /// `rgb`'s own luminance scaled by a blackbody color-temperature approximation for
+    /// `kelvin`, so a tunable-white fade can be driven by Kelvin value instead of a raw
+    /// `w` byte.
+    pub fn from_kelvin(rgb: RGB, kelvin: u16) -> Self {
+        let balance = kelvin_balance(kelvin);
+        let balance_luminance =
+            (balance.r as u16 + balance.g as u16 + balance.b as u16) / 3;
+        let rgb_luminance = (rgb.r as u16 + rgb.g as u16 + rgb.b as u16) / 3;
+        let w = ((rgb_luminance * balance_luminance) / 255) as u8;
+        Self::new(rgb.r, rgb.g, rgb.b, w)
+    }
+}
+
+/// Approximates the blackbody radiation color, in `0..=255` RGB, of a given color
+/// temperature `kelvin`. Implements the common piecewise fit used by e.g. Tasmota's
+/// `colorBalanceFromKelvin`.
+fn kelvin_balance(kelvin: u16) -> RGB {
+    let k = (kelvin as f32 / 100.0).max(1.0);
+
+    let red = if k <= 66.0 {
+        255.0
+    } else {
+        clamp_f32(329.698_73 * libm::powf(k - 60.0, -0.133_2), 0.0, 255.0)
+    };
+
+    let green = if k <= 66.0 {
+        clamp_f32(99.4708 * libm::logf(k) - 161.119_57, 0.0, 255.0)
+    } else {
+        clamp_f32(288.122_16 * libm::powf(k - 60.0, -0.075_5), 0.0, 255.0)
+    };
+
+    let blue = if k >= 66.0 {
+        255.0
+    } else if k <= 19.0 {
+        0.0
+    } else {
+        clamp_f32(138.517_73 * libm::logf(k - 10.0) - 305.044_8, 0.0, 255.0)
+    };
+
+    RGB::new(red as u8, green as u8, blue as u8)
+}
+
+fn clamp_f32(value: f32, min: f32, max: f32) -> f32 {
+    if value < min {
+        min
+    } else if value > max {
+        max
+    } else {
+        value
+    }
+}
+
+/// Represents a dual-white RGB LED with independently addressable cool-white (CW) and
+/// warm-white (WW) channels, for tunable-white strips such as those used by Tasmota's
+/// RGBCW light type.
+#[derive(Clone, Copy)]
+pub struct RGBCW {
+    r: u8,
+    g: u8,
+    b: u8,
+    cw: u8,
+    ww: u8,
+}
+impl RGBCW {
+    pub const fn new(r: u8, g: u8, b: u8, cw: u8, ww: u8) -> Self {
+        Self { r, g, b, cw, ww }
+    }
+}
+impl RgbLedColor for RGBCW {
+    const BIT_COUNT: usize = 8 * 5;
+
+    fn set_color<const DMA_BUFFER_LEN: usize>(
+        &self,
+        led_dma_buffer: &mut LedDmaBuffer<DMA_BUFFER_LEN>,
+        led_index: usize,
+    ) {
+        let (r_rank, g_rank, b_rank) = led_dma_buffer.data_composition.rank();
+        led_dma_buffer.set_byte(self.r, led_index + r_rank * 8);
+        led_dma_buffer.set_byte(self.g, led_index + g_rank * 8);
+        led_dma_buffer.set_byte(self.b, led_index + b_rank * 8);
+        led_dma_buffer.set_byte(self.cw, led_index + 24);
+        led_dma_buffer.set_byte(self.ww, led_index + 32);
+    }
 }
 impl RgbLedColor for RGBW {
     const BIT_COUNT: usize = 8 * 4;
@@ -105,18 +322,13 @@ impl RgbLedColor for RGBW {
         led_dma_buffer: &mut LedDmaBuffer<DMA_BUFFER_LEN>,
         led_index: usize,
     ) {
-        match led_dma_buffer.data_composition {
-            LedDataComposition::GRB => {
-                led_dma_buffer.set_byte(self.g, led_index);
-                led_dma_buffer.set_byte(self.r, led_index + 8);
-            }
-            LedDataComposition::RGB => {
-                led_dma_buffer.set_byte(self.r, led_index);
-                led_dma_buffer.set_byte(self.g, led_index + 8);
-            }
-        }
-        led_dma_buffer.set_byte(self.b, led_index + 16);
-        led_dma_buffer.set_byte(self.w, led_index + 24);
+        let rank = led_dma_buffer.data_composition.rank();
+        let (r_slot, g_slot, b_slot, w_slot) =
+            rgbw_slots(rank, led_dma_buffer.white_position.slot());
+        led_dma_buffer.set_byte(self.r, led_index + r_slot * 8);
+        led_dma_buffer.set_byte(self.g, led_index + g_slot * 8);
+        led_dma_buffer.set_byte(self.b, led_index + b_slot * 8);
+        led_dma_buffer.set_byte(self.w, led_index + w_slot * 8);
     }
 }
 
@@ -132,13 +344,90 @@ pub const fn calc_dma_buffer_length(
     (bits_per_led * led_length) + reset_length
 }
 
+/// Raises `base` to the power of `exp`, using only `u128` integer arithmetic.
+const fn ipow128(base: u128, exp: u32) -> u128 {
+    let mut result: u128 = 1;
+    let mut b = base;
+    let mut e = exp;
+    while e > 0 {
+        if e & 1 == 1 {
+            result *= b;
+        }
+        e >>= 1;
+        if e > 0 {
+            b *= b;
+        }
+    }
+    result
+}
+
+/// Builds a gamma-correction lookup table for `gamma` = `numerator / denominator`
+/// (e.g. `(14, 5)` for a gamma of 2.8), i.e. `table[i] = round(255 * (i/255)^gamma)`,
+/// computed with integer arithmetic only since `no_std` has no `powf`. The derivation below
+/// requires `numerator >= denominator` (i.e. `gamma >= 1.0`); a sub-unity `numerator` is
+/// clamped up to `denominator` (producing the identity table) instead of underflowing.
+/// Intermediate powers (up to `255^numerator * 2^denominator`) are computed in `u128`, so
+/// exponents large enough to overflow it are not supported.
+const fn build_gamma_table(numerator: u32, denominator: u32) -> [u8; 256] {
+    let numerator = if numerator < denominator {
+        denominator
+    } else {
+        numerator
+    };
+    let mut table = [0u8; 256];
+    let mut i = 0;
+    while i < 256 {
+        // Find the largest `y` such that (2y - 1)^denominator * 255^(numerator - denominator)
+        // <= i^numerator * 2^denominator, i.e. y - 1/2 <= 255 * (i/255)^(numerator/denominator),
+        // which is that value rounded to the nearest integer.
+        let target = ipow128(i as u128, numerator) * ipow128(2, denominator);
+        let mut lo: u128 = 0;
+        let mut hi: u128 = 255;
+        while lo < hi {
+            let mid = (lo + hi).div_ceil(2);
+            let candidate =
+                ipow128(2 * mid - 1, denominator) * ipow128(255, numerator - denominator);
+            if candidate <= target {
+                lo = mid;
+            } else {
+                hi = mid - 1;
+            }
+        }
+        table[i] = lo as u8;
+        i += 1;
+    }
+    table
+}
+
+/// A gamma-correction lookup table, applied per-channel in [LedDmaBuffer::set_byte] so that
+/// brightness fades look perceptually linear instead of being crushed in the low end by a
+/// purely linear `brightness/100` scale.
+#[derive(Clone, Copy)]
+pub struct GammaTable([u8; 256]);
+impl GammaTable {
+    /// The default gamma table, using a gamma of ~2.8 as is common for LED strips.
+    pub const DEFAULT: Self = Self(build_gamma_table(14, 5));
+
+    /// Builds a custom gamma table for `gamma` expressed as `numerator/denominator`
+    /// (e.g. `(14, 5)` for a gamma of 2.8). A sub-unity gamma (`numerator < denominator`)
+    /// clamps to the identity table (`gamma = 1.0`) rather than producing garbage.
+    pub const fn new(numerator: u32, denominator: u32) -> Self {
+        Self(build_gamma_table(numerator, denominator))
+    }
+    fn correct(&self, byte: u8) -> u8 {
+        self.0[byte as usize]
+    }
+}
+
 /// A generic DMA Buffer
 pub struct LedDmaBuffer<const DMA_BUFFER_LEN: usize> {
     dma_buffer: [u16; DMA_BUFFER_LEN],
     t1h: u16,
     t0h: u16,
     data_composition: LedDataComposition,
+    white_position: WhitePosition,
     brightness: u8,
+    gamma: Option<GammaTable>,
 }
 
 impl<const DMA_BUFFER_LEN: usize> LedDmaBuffer<DMA_BUFFER_LEN> {
@@ -146,15 +435,31 @@ impl<const DMA_BUFFER_LEN: usize> LedDmaBuffer<DMA_BUFFER_LEN> {
     /// * `t1h` - 1 code, high voltage time value. `t1h` = `1_code_high_voltage_time / data_transfer_time * max_duty_value`
     /// * `t0h` - 0 code, high voltage time value. `t0h` = `0_code_high_voltage_time / data_transfer_time * max_duty_value`
     /// * `data_composition` - The data composition/structure of the led data, found in the LED datasheet.
+    ///
+    /// For RGBW strips, the white byte defaults to [WhitePosition::Fourth] (i.e. last in the
+    /// frame); use [LedDmaBuffer::set_white_position] if your strip places it elsewhere.
     pub fn new(t1h: u16, t0h: u16, data_composition: LedDataComposition) -> Self {
         Self {
             dma_buffer: [0u16; DMA_BUFFER_LEN],
             t1h,
             t0h,
             data_composition,
+            white_position: WhitePosition::Fourth,
             brightness: 100,
+            gamma: None,
         }
     }
+    /// Sets the byte slot the white channel occupies in an RGBW strip's data frame.
+    /// Only affects colors implementing [RgbLedColor] with a white channel, such as [RGBW].
+    pub fn set_white_position(&mut self, white_position: WhitePosition) {
+        self.white_position = white_position;
+    }
+    /// Sets the gamma-correction table, or `None` to disable gamma correction (the default).
+    /// * `gamma` - The gamma table to apply per channel before brightness scaling. Use
+    ///   [GammaTable::DEFAULT] for a standard ~2.8 gamma, or `None` to send raw channel values.
+    pub fn set_gamma(&mut self, gamma: Option<GammaTable>) {
+        self.gamma = gamma;
+    }
     /// Set the DMA buffer
     /// * `led_array` - Array of LEDs
     /// * `rotate` - Rotate LED array
@@ -207,11 +512,49 @@ impl<const DMA_BUFFER_LEN: usize> LedDmaBuffer<DMA_BUFFER_LEN> {
         self.brightness = 100;
         Ok(())
     }
+    /// Set the DMA buffer from an `&[RGB]` array, extracting a white component from each
+    /// pixel via [RGB::to_rgbw] so it renders onto an RGBW-sized DMA buffer with proper
+    /// white-channel usage instead of leaving `w` at `0`.
+    /// * `led_array` - Array of RGB LEDs
+    /// * `mode` - White-extraction algorithm, see [WhiteExtractionMode]
+    /// * `rotate` - Rotate LED array
+    ///     * If `rotate` > 0, rotate right.
+    ///     * If `rotate` < 0, rotate left.
+    pub fn set_dma_buffer_rgbw_from_rgb(
+        &mut self,
+        led_array: &[RGB],
+        mode: WhiteExtractionMode,
+        rotate: Option<i32>,
+    ) -> Result<(), LedDmaError> {
+        if led_array.len() * RGBW::BIT_COUNT > self.dma_buffer.len() {
+            #[cfg(feature = "defmt")]
+            error!(
+                "Led length {} with {} bits per led cannot fit into the DMA buffer of size {}",
+                led_array.len(),
+                RGBW::BIT_COUNT,
+                self.dma_buffer.len()
+            );
+            return Err(LedDmaError::LedArrayLongerThanDmaBuffer);
+        }
+        for (mut led_index, led) in led_array.iter().enumerate() {
+            if let Some(rotate) = rotate {
+                led_index = (led_index as i32 + rotate) as usize % led_array.len();
+            }
+            led_index *= RGBW::BIT_COUNT;
+            led.to_rgbw(mode).set_color(self, led_index);
+        }
+        Ok(())
+    }
     pub fn get_dma_buffer(&self) -> &[u16] {
         &self.dma_buffer
     }
     /// Set a byte in the DMA buffer
     fn set_byte(&mut self, byte: u8, byte_index: usize) {
+        // Apply gamma correction (if enabled) before brightness scaling
+        let byte = match &self.gamma {
+            Some(gamma) => gamma.correct(byte),
+            None => byte,
+        };
         // Adjust byte (r,g,b,w) to correct brightness level
         let adjusted_byte = (f32::from(byte) * f32::from(self.brightness) / 100f32) as u8;
 
@@ -224,3 +567,235 @@ impl<const DMA_BUFFER_LEN: usize> LedDmaBuffer<DMA_BUFFER_LEN> {
         }
     }
 }
+
+/// A logical sub-region of a [LedDmaBuffer], mapping a `(start, len)` LED range onto a
+/// sub-slice of the buffer so several independent animations can be composited into a
+/// single waveform, without recomputing offsets for the whole strip.
+pub struct Segment {
+    start: usize,
+    len: usize,
+    reverse: bool,
+    brightness: u8,
+}
+impl Segment {
+    /// * `start` - Index of this segment's first LED within the physical strip.
+    /// * `len` - Number of LEDs in this segment.
+    pub const fn new(start: usize, len: usize) -> Self {
+        Self {
+            start,
+            len,
+            reverse: false,
+            brightness: 100,
+        }
+    }
+    /// Mirrors this segment, so LED index `0` of `led_array` maps to the *last* LED slot in
+    /// the segment instead of the first.
+    pub fn set_reverse(&mut self, reverse: bool) {
+        self.reverse = reverse;
+    }
+    /// Sets this segment's own brightness, independent of the rest of the strip.
+    /// * `brightness` - Brightness level, `0%` - `100%`
+    pub fn set_brightness(&mut self, brightness: u8) -> Result<(), LedDmaError> {
+        if brightness > 100 {
+            #[cfg(feature = "defmt")]
+            error!("Brightness is greater than 100%, it is {}%.", brightness);
+            return Err(LedDmaError::BrightnessOver100);
+        }
+        self.brightness = brightness;
+        Ok(())
+    }
+    /// Writes `led_array` into this segment's bit offsets,
+    /// `(start * T::BIT_COUNT)..((start + len) * T::BIT_COUNT)`, of `led_dma_buffer`, without
+    /// touching any other segment's bits.
+    pub fn set_dma_buffer<T: RgbLedColor, const DMA_BUFFER_LEN: usize>(
+        &self,
+        led_dma_buffer: &mut LedDmaBuffer<DMA_BUFFER_LEN>,
+        led_array: &[T],
+    ) -> Result<(), LedDmaError> {
+        if led_array.len() > self.len
+            || (self.start + self.len) * T::BIT_COUNT > led_dma_buffer.dma_buffer.len()
+        {
+            #[cfg(feature = "defmt")]
+            error!(
+                "Led length {} with {} bits per led cannot fit into segment of length {} at start {}",
+                led_array.len(),
+                T::BIT_COUNT,
+                self.len,
+                self.start
+            );
+            return Err(LedDmaError::LedArrayLongerThanDmaBuffer);
+        }
+        let previous_brightness = led_dma_buffer.brightness;
+        led_dma_buffer.brightness = self.brightness;
+        for (i, led) in led_array.iter().enumerate() {
+            let position = if self.reverse { self.len - 1 - i } else { i };
+            let led_index = (self.start + position) * T::BIT_COUNT;
+            led.set_color(led_dma_buffer, led_index);
+        }
+        led_dma_buffer.brightness = previous_brightness;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hsv_to_rgb_primary_colors() {
+        let red = HSV::new(0, 255, 255).to_rgb();
+        assert_eq!((red.r, red.g, red.b), (255, 0, 0));
+
+        // Sector boundaries land on the transition colors, not the pure primaries.
+        let yellow = HSV::new(43, 255, 255).to_rgb();
+        assert_eq!((yellow.r, yellow.g, yellow.b), (255, 255, 0));
+    }
+
+    #[test]
+    fn hsv_to_rgb_zero_saturation_is_gray() {
+        let gray = HSV::new(123, 0, 200).to_rgb();
+        assert_eq!((gray.r, gray.g, gray.b), (200, 200, 200));
+    }
+
+    #[test]
+    fn hsv_to_rgb_wraps_across_all_six_sectors() {
+        // Every sector should hand back a color with `v` as its brightest channel.
+        for h in (0..=255u8).step_by(17) {
+            let rgb = HSV::new(h, 255, 255).to_rgb();
+            assert_eq!(rgb.r.max(rgb.g).max(rgb.b), 255);
+        }
+    }
+
+    #[test]
+    fn gamma_table_endpoints_and_monotonic() {
+        let table = GammaTable::DEFAULT;
+        assert_eq!(table.0[0], 0);
+        assert_eq!(table.0[255], 255);
+        for i in 1..256 {
+            assert!(table.0[i] >= table.0[i - 1]);
+        }
+    }
+
+    #[test]
+    fn gamma_table_crushes_low_end_below_identity() {
+        // A gamma > 1.0 table should sit at or below the identity mapping everywhere.
+        let table = GammaTable::DEFAULT;
+        for i in 0..256 {
+            assert!(table.0[i] as usize <= i);
+        }
+    }
+
+    #[test]
+    fn gamma_table_sub_unity_clamps_to_identity_instead_of_underflowing() {
+        // numerator < denominator used to underflow `numerator - denominator`; it must now
+        // clamp to gamma = 1.0 (the identity table) instead.
+        let clamped = GammaTable::new(1, 2);
+        let identity = GammaTable::new(2, 2);
+        assert_eq!(clamped.0, identity.0);
+        assert_eq!(identity.0[100], 100);
+    }
+
+    #[test]
+    fn kelvin_balance_is_neutral_white_around_6600k() {
+        // This piecewise fit is tuned so ~6600K lands on (almost) neutral white.
+        let balance = kelvin_balance(6600);
+        assert!(balance.r >= 250);
+        assert!(balance.g >= 250);
+        assert!(balance.b >= 250);
+    }
+
+    #[test]
+    fn kelvin_balance_warm_is_reddish_cool_is_bluish() {
+        let warm = kelvin_balance(2000);
+        assert!(warm.r > warm.b);
+
+        let cool = kelvin_balance(10000);
+        assert!(cool.b > cool.r);
+    }
+
+    #[test]
+    fn rgbw_from_kelvin_preserves_rgb_channels() {
+        let rgbw = RGBW::from_kelvin(RGB::new(10, 20, 30), 5000);
+        assert_eq!((rgbw.r, rgbw.g, rgbw.b), (10, 20, 30));
+    }
+
+    #[test]
+    fn rgbw_from_kelvin_scales_white_by_balance_luminance() {
+        // A neutral-white RGB at the fit's ~neutral kelvin point should derive a white
+        // channel near full brightness, not a raw, untinted byte.
+        let rgbw = RGBW::from_kelvin(RGB::new(255, 255, 255), 6600);
+        assert!(rgbw.w >= 250);
+
+        // A very warm color temperature should derive a dimmer white than a neutral one,
+        // since the blue channel of the balance is heavily attenuated.
+        let warm_rgbw = RGBW::from_kelvin(RGB::new(255, 255, 255), 1000);
+        assert!(warm_rgbw.w < rgbw.w);
+    }
+
+    #[test]
+    fn rgbw_slots_white_last_leaves_rgb_in_rank_order() {
+        // Default layout: white in slot 3, RGB in rank order fill slots 0..3 unchanged.
+        assert_eq!(rgbw_slots((0, 1, 2), 3), (0, 1, 2, 3));
+    }
+
+    #[test]
+    fn rgbw_slots_white_first_shifts_rgb_down() {
+        // White takes slot 0, so the three remaining slots (1, 2, 3) fill in rank order.
+        assert_eq!(rgbw_slots((0, 1, 2), 0), (1, 2, 3, 0));
+    }
+
+    #[test]
+    fn rgbw_slots_respects_rank_with_white_in_middle() {
+        // White takes slot 1; the leftover slots (0, 2, 3) are assigned by rank, so a
+        // non-identity rank (e.g. BRG's (1, 2, 0)) permutes which leftover slot each
+        // channel lands in.
+        assert_eq!(rgbw_slots((1, 2, 0), 1), (2, 3, 0, 1));
+    }
+
+    /// Decodes 8 consecutive DMA buffer entries back into a byte, given the `t1h` value used
+    /// to encode a `1` bit (mirrors [LedDmaBuffer::set_byte]'s bit order, MSB first).
+    fn decode_byte(bits: &[u16], t1h: u16) -> u8 {
+        let mut byte = 0u8;
+        for (i, &bit) in bits.iter().enumerate() {
+            if bit == t1h {
+                byte |= 1 << (7 - i);
+            }
+        }
+        byte
+    }
+
+    #[test]
+    fn segment_writes_only_its_own_bit_range() {
+        // t1h/t0h are distinct from the buffer's initial 0, so untouched LEDs stay
+        // trivially distinguishable from written ones.
+        let mut buffer: LedDmaBuffer<{ 5 * 24 }> = LedDmaBuffer::new(1, 2, LedDataComposition::RGB);
+        let segment = Segment::new(2, 3);
+        let colors = [(0x12u8, 0x34u8, 0x56u8), (0x78, 0x9a, 0xbc), (0xde, 0xf0, 0x11)];
+        let leds = colors.map(|(r, g, b)| RGB::new(r, g, b));
+        segment.set_dma_buffer(&mut buffer, &leds).unwrap();
+
+        let dma = buffer.get_dma_buffer();
+        // LEDs 0 and 1, outside the segment, are untouched.
+        assert!(dma[0..48].iter().all(|&v| v == 0));
+        for (i, &(r, g, b)) in colors.iter().enumerate() {
+            let base = (2 + i) * 24;
+            assert_eq!(decode_byte(&dma[base..base + 8], 1), r);
+            assert_eq!(decode_byte(&dma[base + 8..base + 16], 1), g);
+            assert_eq!(decode_byte(&dma[base + 16..base + 24], 1), b);
+        }
+    }
+
+    #[test]
+    fn segment_reverse_flips_led_order_within_its_range() {
+        let mut buffer: LedDmaBuffer<{ 2 * 24 }> = LedDmaBuffer::new(1, 2, LedDataComposition::RGB);
+        let mut segment = Segment::new(0, 2);
+        segment.set_reverse(true);
+        let leds = [RGB::new(1, 2, 3), RGB::new(4, 5, 6)];
+        segment.set_dma_buffer(&mut buffer, &leds).unwrap();
+
+        let dma = buffer.get_dma_buffer();
+        // `leds[0]` is the last physical LED in the segment, and vice versa.
+        assert_eq!(decode_byte(&dma[0..8], 1), 4);
+        assert_eq!(decode_byte(&dma[24..32], 1), 1);
+    }
+}